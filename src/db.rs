@@ -1,28 +1,36 @@
-use crate::processes::{Process, QUERY_INTERVAL};
+use crate::paths::DATA_FOLDER;
+use crate::process::{Process, QUERY_INTERVAL};
 use chrono::NaiveDateTime;
 use once_cell::sync::Lazy;
 use rusqlite::{params, Connection, Result};
-use std::{collections::HashSet, fs::create_dir_all, time::SystemTime};
+use std::{collections::HashSet, fs::create_dir_all, path::PathBuf, time::SystemTime};
 
-static DATA_FOLDER: Lazy<String> = Lazy::new(|| {
-	let username = whoami::username();
+static DATABASE_PATH: Lazy<PathBuf> = Lazy::new(|| DATA_FOLDER.join("db.sqlite"));
 
-	format!("C:\\Users\\{}\\AppData\\Local\\ProcessTracker", username)
-});
+pub(crate) fn setup_database() -> Result<Connection, rusqlite::Error> {
+	create_dir_all(DATA_FOLDER.as_path()).expect("Failed to create data folder");
 
-static DATABASE_PATH: Lazy<String> = Lazy::new(|| DATA_FOLDER.to_string() + "\\db.sqlite");
+	let conn = Connection::open(DATABASE_PATH.as_path())?;
 
-pub(crate) fn setup_database() -> Result<Connection, rusqlite::Error> {
-	create_dir_all(DATA_FOLDER.to_string()).expect("Failed to create data folder");
+	create_schema(&conn)?;
 
-	let conn = Connection::open(DATABASE_PATH.to_string())?;
+	Ok(conn)
+}
 
+/// Creates the `processes`/`process_times` tables if they don't exist yet, then migrates them to
+/// the current column set
+///
+/// `CREATE TABLE IF NOT EXISTS` is a no-op against a database file created by an older build, so
+/// every column added after the initial release also needs an explicit `ALTER TABLE` migration,
+/// or upgrading in place hits "no such column" on the next `update_processes` call
+pub(crate) fn create_schema(conn: &Connection) -> Result<()> {
 	conn.execute(
 		"CREATE TABLE IF NOT EXISTS processes (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             name TEXT NOT NULL,
             pretty_name TEXT NOT NULL,
             path TEXT NOT NULL,
+            parent_id INTEGER REFERENCES processes(id) ON DELETE SET NULL,
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )",
 		(),
@@ -33,25 +41,47 @@ pub(crate) fn setup_database() -> Result<Connection, rusqlite::Error> {
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             process_id INTEGER NOT NULL REFERENCES processes(id) ON DELETE CASCADE,
 			running_time INTEGER NOT NULL DEFAULT 0,
+			active_time INTEGER NOT NULL DEFAULT 0,
+			cpu_time INTEGER NOT NULL DEFAULT 0,
+			disk_read_bytes INTEGER NOT NULL DEFAULT 0,
+			disk_write_bytes INTEGER NOT NULL DEFAULT 0,
             created_at DATETIME DEFAULT CURRENT_TIMESTAMP
         )",
 		(),
 	)?;
 
-	Ok(conn)
+	add_column_if_missing(conn, "processes", "parent_id INTEGER REFERENCES processes(id) ON DELETE SET NULL")?;
+	add_column_if_missing(conn, "process_times", "active_time INTEGER NOT NULL DEFAULT 0")?;
+	add_column_if_missing(conn, "process_times", "cpu_time INTEGER NOT NULL DEFAULT 0")?;
+	add_column_if_missing(conn, "process_times", "disk_read_bytes INTEGER NOT NULL DEFAULT 0")?;
+	add_column_if_missing(conn, "process_times", "disk_write_bytes INTEGER NOT NULL DEFAULT 0")?;
+
+	Ok(())
+}
+
+/// Adds a column to an already-existing table, tolerating the "duplicate column name" error SQLite
+/// raises when it was already added (by a fresh `CREATE TABLE`, or a previous run of this migration)
+fn add_column_if_missing(conn: &Connection, table: &str, column_def: &str) -> Result<()> {
+	match conn.execute(&format!("ALTER TABLE {} ADD COLUMN {}", table, column_def), ()) {
+		Ok(_) => Ok(()),
+		Err(rusqlite::Error::SqliteFailure(_, Some(message))) if message.contains("duplicate column name") => Ok(()),
+		Err(err) => Err(err),
+	}
 }
 
 /// Updates the database with the latest process list
 ///
 /// The way this works is that it first will get the latest process times for each process from the last hour
 /// It will then iterate over this list and find the corresponding process in the current process list
-/// If it finds a match, it calculate the time at which the row was created and adds the time since then to the running time
+/// If it finds a match, it calculate the time at which the row was created and adds the time since then to the running time,
+/// and accumulates this interval's CPU time and disk I/O onto the running totals. The same elapsed time is
+/// added to the active time too, but only if the process currently owns the foreground window.
 /// If it doesn't find a match, it will create a new process row
 ///
 /// After this, it will iterate over the current process list and insert any processes that don't exist in the database
 pub(crate) fn update_processes(conn: &Connection, process_list: &Vec<Process>) -> Result<()> {
 	let mut last_processes_stmt = conn.prepare(
-		"SELECT path, process_id, running_time, MAX(process_times.created_at)
+		"SELECT path, process_id, running_time, active_time, cpu_time, disk_read_bytes, disk_write_bytes, MAX(process_times.created_at)
 		FROM process_times
 		INNER JOIN processes ON processes.id = process_times.process_id
 		WHERE process_times.created_at > datetime('now', '-1 hour')
@@ -69,13 +99,26 @@ pub(crate) fn update_processes(conn: &Connection, process_list: &Vec<Process>) -
 		{
 			let process_id = last_process.get::<usize, i64>(1).unwrap();
 			let running_time = last_process.get::<usize, u64>(2).unwrap();
-			let created_at = last_process.get::<usize, String>(3).unwrap();
+			let active_time = last_process.get::<usize, u64>(3).unwrap();
+			let cpu_time = last_process.get::<usize, u64>(4).unwrap();
+			let disk_read_bytes = last_process.get::<usize, u64>(5).unwrap();
+			let disk_write_bytes = last_process.get::<usize, u64>(6).unwrap();
+			let created_at = last_process.get::<usize, String>(7).unwrap();
 
-			let running_time = get_new_running_time(created_at, running_time);
+			let elapsed_time = get_elapsed_time(&created_at);
+			let running_time = get_new_running_time(elapsed_time, running_time);
+			let active_time = if process.is_active {
+				get_new_running_time(elapsed_time, active_time)
+			} else {
+				active_time
+			};
+			let cpu_time = cpu_time + process.cpu_time;
+			let disk_read_bytes = disk_read_bytes + process.disk_read_bytes;
+			let disk_write_bytes = disk_write_bytes + process.disk_write_bytes;
 
 			conn.execute(
-				"UPDATE process_times SET running_time = ?1 WHERE process_id = ?2",
-				params![running_time, process_id],
+				"UPDATE process_times SET running_time = ?1, active_time = ?2, cpu_time = ?3, disk_read_bytes = ?4, disk_write_bytes = ?5 WHERE process_id = ?6",
+				params![running_time, active_time, cpu_time, disk_read_bytes, disk_write_bytes, process_id],
 			)?;
 
 			existing_processes.insert(process.path.clone());
@@ -87,44 +130,186 @@ pub(crate) fn update_processes(conn: &Connection, process_list: &Vec<Process>) -
 			continue;
 		}
 
-		let process_id = match conn.query_row(
-			"SELECT id FROM processes WHERE name = ?1",
-			params![process.name],
-			|row| row.get(0),
-		) {
-			Ok(id) => id,
+		let process_id = match conn.query_row("SELECT id FROM processes WHERE name = ?1", params![process.name], |row| row.get(0)) {
+			Ok(id) => {
+				let id: i64 = id;
+
+				// The row may have been created as a placeholder by get_or_create_process_id, before this
+				// process was ever actually observed, in which case path is still the empty placeholder
+				// value. Bring it up to date so future polls can match this process by path again
+				conn.execute(
+					"UPDATE processes SET path = ?1, pretty_name = ?2 WHERE id = ?3",
+					params![process.path, process.pretty_name, id],
+				)?;
+
+				id
+			}
 			Err(_) => {
+				// Attribute the process to its top-level ancestor, so helper subprocesses
+				// (browser renderers, webview hosts, etc.) roll up into the application that spawned them
+				let parent_id = match &process.parent_name {
+					Some(parent_name) => Some(get_or_create_process_id(conn, parent_name, parent_name, "")?),
+					None => None,
+				};
+
 				conn.execute(
-					"INSERT INTO processes (name, pretty_name, path) VALUES (?1, ?2, ?3)",
-					params![process.name, process.pretty_name, process.path],
+					"INSERT INTO processes (name, pretty_name, path, parent_id) VALUES (?1, ?2, ?3, ?4)",
+					params![process.name, process.pretty_name, process.path, parent_id],
 				)?;
 
 				conn.last_insert_rowid()
 			}
 		};
 
+		let active_time = if process.is_active { QUERY_INTERVAL.as_secs() } else { 0 };
+
 		conn.execute(
-			"INSERT INTO process_times (process_id) VALUES (?1)",
-			params![process_id],
+			"INSERT INTO process_times (process_id, active_time, cpu_time, disk_read_bytes, disk_write_bytes) VALUES (?1, ?2, ?3, ?4, ?5)",
+			params![process_id, active_time, process.cpu_time, process.disk_read_bytes, process.disk_write_bytes],
 		)?;
 	}
 
 	Ok(())
 }
 
-/// Returns an approximation for the time elapsed between the last time the process was updated and now
-///
-/// In the even that the time was updated more than 2x the query interval, it will just return the query interval
-/// Given that probably means the program was closed and reopened
+/// Returns the id of the `processes` row with the given name, creating a placeholder row for it if it doesn't exist yet
 ///
-/// Otherwise, it will return the time elapsed since the last update
-fn get_new_running_time(created_at: String, running_time: u64) -> u64 {
-	let created_at = NaiveDateTime::parse_from_str(&created_at, "%Y-%m-%d %H:%M:%S").unwrap();
+/// Used to resolve a process' parent by name alone, since at the time a child is first seen its parent
+/// may not have appeared in the current process list (e.g. it has no visible window of its own)
+fn get_or_create_process_id(conn: &Connection, name: &str, pretty_name: &str, path: &str) -> Result<i64> {
+	match conn.query_row("SELECT id FROM processes WHERE name = ?1", params![name], |row| row.get(0)) {
+		Ok(id) => Ok(id),
+		Err(_) => {
+			conn.execute(
+				"INSERT INTO processes (name, pretty_name, path) VALUES (?1, ?2, ?3)",
+				params![name, pretty_name, path],
+			)?;
 
-	let elapsed_time = SystemTime::UNIX_EPOCH.elapsed().unwrap().as_secs() - created_at.timestamp() as u64;
+			Ok(conn.last_insert_rowid())
+		}
+	}
+}
+
+/// Returns the time elapsed between the last time the process was updated and now
+fn get_elapsed_time(created_at: &str) -> u64 {
+	let created_at = NaiveDateTime::parse_from_str(created_at, "%Y-%m-%d %H:%M:%S").unwrap();
+
+	SystemTime::UNIX_EPOCH.elapsed().unwrap().as_secs() - created_at.timestamp() as u64
+}
+
+/// Adds the elapsed time to a running total, as an approximation for the time a process spent in some state
+///
+/// In the event that the elapsed time is more than 2x the query interval, it will just add the query interval
+/// Given that probably means the program was closed and reopened
+fn get_new_running_time(elapsed_time: u64, running_time: u64) -> u64 {
 	if elapsed_time > (QUERY_INTERVAL.as_secs() * 2) {
 		return running_time + QUERY_INTERVAL.as_secs();
 	}
 
 	running_time + elapsed_time
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Simulates upgrading a database created by a version of this tool that predates the
+	/// parent_id/active_time/cpu_time/disk_read_bytes/disk_write_bytes columns
+	fn old_schema_conn() -> Connection {
+		let conn = Connection::open_in_memory().unwrap();
+
+		conn.execute(
+			"CREATE TABLE processes (
+				id INTEGER PRIMARY KEY AUTOINCREMENT,
+				name TEXT NOT NULL,
+				pretty_name TEXT NOT NULL,
+				path TEXT NOT NULL,
+				created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+			)",
+			(),
+		)
+		.unwrap();
+
+		conn.execute(
+			"CREATE TABLE process_times (
+				id INTEGER PRIMARY KEY AUTOINCREMENT,
+				process_id INTEGER NOT NULL REFERENCES processes(id) ON DELETE CASCADE,
+				running_time INTEGER NOT NULL DEFAULT 0,
+				created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+			)",
+			(),
+		)
+		.unwrap();
+
+		conn
+	}
+
+	#[test]
+	fn create_schema_migrates_a_database_missing_the_newer_columns() {
+		let conn = old_schema_conn();
+
+		create_schema(&conn).unwrap();
+
+		conn.execute("INSERT INTO processes (name, pretty_name, path) VALUES ('a.exe', 'A', '')", ())
+			.unwrap();
+		let process_id = conn.last_insert_rowid();
+
+		conn.execute(
+			"INSERT INTO process_times (process_id, active_time, cpu_time, disk_read_bytes, disk_write_bytes) VALUES (?1, 1, 2, 3, 4)",
+			params![process_id],
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn create_schema_is_idempotent_against_a_fresh_database() {
+		let conn = Connection::open_in_memory().unwrap();
+
+		create_schema(&conn).unwrap();
+		create_schema(&conn).unwrap();
+	}
+
+	#[test]
+	fn get_new_running_time_adds_elapsed_time_normally() {
+		assert_eq!(get_new_running_time(5, 100), 105);
+	}
+
+	#[test]
+	fn get_new_running_time_caps_a_large_gap_at_one_query_interval() {
+		let gap = QUERY_INTERVAL.as_secs() * 3;
+		assert_eq!(get_new_running_time(gap, 100), 100 + QUERY_INTERVAL.as_secs());
+	}
+
+	#[test]
+	fn update_processes_heals_a_placeholder_parents_path() {
+		let conn = Connection::open_in_memory().unwrap();
+		create_schema(&conn).unwrap();
+
+		// Simulate a child process being observed and inserted before its own parent ever is,
+		// which creates a placeholder row for the parent with an empty path
+		let parent_id = get_or_create_process_id(&conn, "chrome.exe", "chrome.exe", "").unwrap();
+
+		let process_list = vec![Process {
+			name: "chrome.exe".to_string(),
+			pretty_name: "Google Chrome".to_string(),
+			path: "C:\\chrome.exe".to_string(),
+			memory: 0,
+			cpu_time: 0,
+			disk_read_bytes: 0,
+			disk_write_bytes: 0,
+			parent_name: None,
+			is_active: false,
+		}];
+
+		update_processes(&conn, &process_list).unwrap();
+
+		let (path, pretty_name): (String, String) = conn
+			.query_row("SELECT path, pretty_name FROM processes WHERE id = ?1", params![parent_id], |row| {
+				Ok((row.get(0)?, row.get(1)?))
+			})
+			.unwrap();
+
+		assert_eq!(path, "C:\\chrome.exe");
+		assert_eq!(pretty_name, "Google Chrome");
+	}
+}
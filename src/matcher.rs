@@ -0,0 +1,317 @@
+use crate::paths::DATA_FOLDER;
+use crate::process::Process;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+
+const RULES_FILE_NAME: &str = "rules.toml";
+
+// `CefSharp.BrowserSubprocess.exe`, `msedgewebview2.exe`, and `steamwebhelper.exe` used to be
+// hand-listed here, but their time is now rolled up into their top-level ancestor via `parent_id`
+// (see `get_process_list` in the source backends), so ignoring them outright is no longer needed
+const IGNORED_PROCESSES: [&str; 17] = [
+	// spell-checker:disable
+	"cargo.exe",
+	"crashpad_handler.exe",
+	"explorer.exe",
+	"GoogleDriveFS.exe",
+	"LSB.exe",
+	"MbamBgNativeMsg.exe",
+	"mbamtray.exe",
+	"MSPCManagerService.exe",
+	"nvcontainer.exe",
+	"NVIDIA Share.exe",
+	"NVIDIA Web Helper.exe",
+	"nvsphelper64.exe",
+	"OneDrive.exe",
+	"QSHelper.exe",
+	"Razer Synapse Service Process.exe",
+	"vctip.exe",
+	"XboxGameBarSpotify.exe",
+	// spell-checker:enable
+];
+
+const IGNORED_PATH_PREFIXES: [&str; 5] = [
+	// spell-checker:disable
+	"C:\\Program Files (x86)\\Lenovo\\VantageService",
+	"C:\\Program Files\\Git",
+	"C:\\Program Files\\PowerToys\\PowerToys.",
+	"C:\\Program Files\\WindowsApps\\MicrosoftWindows.Client",
+	"C:\\Windows",
+	// spell-checker:enable
+];
+
+const IGNORED_PATH_SUFFIXES_IN_HOME: [&str; 3] = [".rustup", ".vscode", ".wakatime"];
+
+const SPECIAL_CASES: [(&str, &str); 2] = [
+	("Spotify.exe", "Spotify"),
+	("datagrip64.exe", "DataGrip"), // spell-checker:disable-line
+];
+
+/// A single condition a process is checked against
+pub(crate) enum Matcher {
+	NameEquals(String),
+	NameRegex(Regex),
+	PathPrefix(String),
+	MemoryAbove(u64),
+}
+
+impl Matcher {
+	#[must_use]
+	pub(crate) fn matches(&self, process: &Process) -> bool {
+		match self {
+			Matcher::NameEquals(name) => process.name == *name,
+			Matcher::NameRegex(regex) => regex.is_match(&process.name),
+			Matcher::PathPrefix(prefix) => process.path.starts_with(prefix.as_str()),
+			Matcher::MemoryAbove(threshold) => process.memory > *threshold,
+		}
+	}
+}
+
+/// What to do with a process once every [`Matcher`] in its [`Rule`] matches
+pub(crate) enum Action {
+	Ignore,
+	Rename { pretty_name: String },
+}
+
+/// A set of matchers that must all match, paired with the action to take when they do
+pub(crate) struct Rule {
+	matchers: Vec<Matcher>,
+	pub(crate) action: Action,
+}
+
+impl Rule {
+	#[must_use]
+	pub(crate) fn matches(&self, process: &Process) -> bool {
+		self.matchers.iter().all(|matcher| matcher.matches(process))
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MatcherConfig {
+	NameEquals { value: String },
+	NameRegex { value: String },
+	PathPrefix { value: String },
+	MemoryAbove { value: u64 },
+}
+
+impl TryFrom<MatcherConfig> for Matcher {
+	type Error = regex::Error;
+
+	fn try_from(config: MatcherConfig) -> Result<Self, Self::Error> {
+		Ok(match config {
+			MatcherConfig::NameEquals { value } => Matcher::NameEquals(value),
+			MatcherConfig::NameRegex { value } => Matcher::NameRegex(Regex::new(&value)?),
+			MatcherConfig::PathPrefix { value } => Matcher::PathPrefix(value),
+			MatcherConfig::MemoryAbove { value } => Matcher::MemoryAbove(value),
+		})
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ActionConfig {
+	Ignore,
+	Rename { pretty_name: String },
+}
+
+impl From<ActionConfig> for Action {
+	fn from(config: ActionConfig) -> Self {
+		match config {
+			ActionConfig::Ignore => Action::Ignore,
+			ActionConfig::Rename { pretty_name } => Action::Rename { pretty_name },
+		}
+	}
+}
+
+#[derive(Deserialize)]
+struct RuleConfig {
+	matchers: Vec<MatcherConfig>,
+	#[serde(flatten)]
+	action: ActionConfig,
+}
+
+#[derive(Deserialize, Default)]
+struct RulesConfig {
+	#[serde(default)]
+	rule: Vec<RuleConfig>,
+}
+
+pub(crate) static RULES: Lazy<Vec<Rule>> = Lazy::new(|| load_rules(&DATA_FOLDER.join(RULES_FILE_NAME)));
+
+/// Loads the user's matcher rules from `rules.toml` in the data folder, falling back to the
+/// built-in defaults when the file doesn't exist or fails to parse
+fn load_rules(path: &std::path::Path) -> Vec<Rule> {
+	let contents = match std::fs::read_to_string(path) {
+		Ok(contents) => contents,
+		Err(_) => return default_rules(),
+	};
+
+	let config: RulesConfig = match toml::from_str(&contents) {
+		Ok(config) => config,
+		Err(err) => {
+			eprintln!(
+				"Failed to parse {}: {}, falling back to built-in defaults",
+				path.display(),
+				err
+			);
+			return default_rules();
+		}
+	};
+
+	config
+		.rule
+		.into_iter()
+		.filter_map(|rule| {
+			if rule.matchers.is_empty() {
+				eprintln!("Rule in {} has no matchers, which would match every process, so it's being skipped", path.display());
+				return None;
+			}
+
+			let matchers = rule
+				.matchers
+				.into_iter()
+				.map(Matcher::try_from)
+				.collect::<Result<Vec<Matcher>, _>>()
+				.ok()?;
+
+			Some(Rule {
+				matchers,
+				action: rule.action.into(),
+			})
+		})
+		.collect()
+}
+
+/// The rules the tracker ships with, mirroring the historical hardcoded ignore/special-case arrays
+fn default_rules() -> Vec<Rule> {
+	let mut rules: Vec<Rule> = IGNORED_PROCESSES
+		.iter()
+		.map(|name| Rule {
+			matchers: vec![Matcher::NameEquals((*name).to_string())],
+			action: Action::Ignore,
+		})
+		.collect();
+
+	rules.extend(IGNORED_PATH_PREFIXES.iter().map(|prefix| Rule {
+		matchers: vec![Matcher::PathPrefix((*prefix).to_string())],
+		action: Action::Ignore,
+	}));
+
+	let username = whoami::username();
+	rules.extend(IGNORED_PATH_SUFFIXES_IN_HOME.iter().map(|suffix| Rule {
+		matchers: vec![Matcher::PathPrefix(format!("C:\\Users\\{}\\{}", username, suffix))],
+		action: Action::Ignore,
+	}));
+
+	rules.extend(SPECIAL_CASES.iter().map(|(name, pretty_name)| Rule {
+		matchers: vec![Matcher::NameEquals((*name).to_string())],
+		action: Action::Rename {
+			pretty_name: (*pretty_name).to_string(),
+		},
+	}));
+
+	rules
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn process(name: &str, path: &str, memory: u64) -> Process {
+		Process {
+			name: name.to_string(),
+			pretty_name: String::new(),
+			path: path.to_string(),
+			memory,
+			cpu_time: 0,
+			disk_read_bytes: 0,
+			disk_write_bytes: 0,
+			parent_name: None,
+			is_active: false,
+		}
+	}
+
+	#[test]
+	fn name_equals_matches_the_exact_name_only() {
+		let matcher = Matcher::NameEquals("chrome.exe".to_string());
+		assert!(matcher.matches(&process("chrome.exe", "C:\\chrome.exe", 0)));
+		assert!(!matcher.matches(&process("firefox.exe", "C:\\firefox.exe", 0)));
+	}
+
+	#[test]
+	fn name_regex_matches_the_pattern() {
+		let matcher = Matcher::NameRegex(Regex::new(r"^chrome").unwrap());
+		assert!(matcher.matches(&process("chrome.exe", "C:\\chrome.exe", 0)));
+		assert!(!matcher.matches(&process("firefox.exe", "C:\\firefox.exe", 0)));
+	}
+
+	#[test]
+	fn path_prefix_matches_the_prefix() {
+		let matcher = Matcher::PathPrefix("C:\\Windows".to_string());
+		assert!(matcher.matches(&process("svchost.exe", "C:\\Windows\\svchost.exe", 0)));
+		assert!(!matcher.matches(&process("chrome.exe", "C:\\Program Files\\chrome.exe", 0)));
+	}
+
+	#[test]
+	fn memory_above_matches_when_over_the_threshold() {
+		let matcher = Matcher::MemoryAbove(1024);
+		assert!(matcher.matches(&process("chrome.exe", "C:\\chrome.exe", 2048)));
+		assert!(!matcher.matches(&process("chrome.exe", "C:\\chrome.exe", 512)));
+	}
+
+	#[test]
+	fn rule_requires_every_matcher_to_match() {
+		let rule = Rule {
+			matchers: vec![Matcher::NameEquals("chrome.exe".to_string()), Matcher::MemoryAbove(1024)],
+			action: Action::Ignore,
+		};
+
+		assert!(rule.matches(&process("chrome.exe", "C:\\chrome.exe", 2048)));
+		assert!(!rule.matches(&process("chrome.exe", "C:\\chrome.exe", 512)));
+		assert!(!rule.matches(&process("firefox.exe", "C:\\firefox.exe", 2048)));
+	}
+
+	#[test]
+	fn default_rules_still_ignore_the_built_in_ignore_list() {
+		let rules = default_rules();
+		let explorer = process("explorer.exe", "C:\\Windows\\explorer.exe", 0);
+		assert!(rules.iter().any(|rule| matches!(rule.action, Action::Ignore) && rule.matches(&explorer)));
+	}
+
+	#[test]
+	fn load_rules_skips_a_rule_with_no_matchers() {
+		let path = std::env::temp_dir().join("process-tracker-test-empty-matchers-rules.toml");
+		std::fs::write(
+			&path,
+			r#"
+			[[rule]]
+			matchers = []
+			action = "ignore"
+
+			[[rule]]
+			matchers = [{ type = "name_equals", value = "chrome.exe" }]
+			action = "ignore"
+			"#,
+		)
+		.unwrap();
+
+		let rules = load_rules(&path);
+		std::fs::remove_file(&path).unwrap();
+
+		assert_eq!(rules.len(), 1);
+		assert!(rules[0].matches(&process("chrome.exe", "C:\\chrome.exe", 0)));
+	}
+
+	#[test]
+	fn default_rules_still_rename_special_cases() {
+		let rules = default_rules();
+		let spotify = process("Spotify.exe", "C:\\Spotify.exe", 0);
+		let renamed = rules.iter().find_map(|rule| match &rule.action {
+			Action::Rename { pretty_name } if rule.matches(&spotify) => Some(pretty_name.clone()),
+			_ => None,
+		});
+		assert_eq!(renamed.as_deref(), Some("Spotify"));
+	}
+}
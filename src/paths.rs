@@ -0,0 +1,9 @@
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+
+/// Directory where the tracker stores its database and user configuration
+pub(crate) static DATA_FOLDER: Lazy<PathBuf> = Lazy::new(|| {
+	dirs::data_local_dir()
+		.expect("Could not determine the local data directory for this platform")
+		.join("ProcessTracker")
+});
@@ -0,0 +1,174 @@
+use crate::matcher::{Action, RULES};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::time::Duration;
+use sysinfo::ProcessStatus;
+
+/// A process that is relevant to the user, as reported by one of the [`crate::source::ProcessSource`] backends
+#[allow(dead_code)]
+pub(crate) struct Process {
+	/// Name of the process executable
+	pub(crate) name: String,
+	/// Pretty version of the process name
+	pub(crate) pretty_name: String,
+	/// Path to the executable
+	pub(crate) path: String,
+	/// Memory, in bytes, the process is currently using
+	pub(crate) memory: u64,
+	/// CPU time, in seconds, used by the process since the previous [`crate::source::ProcessSource::get_process_list`] call
+	pub(crate) cpu_time: u64,
+	/// Bytes read from disk by the process since the previous [`crate::source::ProcessSource::get_process_list`] call
+	pub(crate) disk_read_bytes: u64,
+	/// Bytes written to disk by the process since the previous [`crate::source::ProcessSource::get_process_list`] call
+	pub(crate) disk_write_bytes: u64,
+	/// Name of the top-level process this one descends from, if any (e.g. a browser renderer's parent browser)
+	pub(crate) parent_name: Option<String>,
+	/// Whether this process currently owns the foreground/focused window, as opposed to merely having one open
+	pub(crate) is_active: bool,
+}
+
+const NAME_SEPARATORS: [&str; 3] = ["-", "_", "."];
+const EXTENSION: &str = ".exe";
+
+pub(crate) static QUERY_INTERVAL: Lazy<Duration> = Lazy::new(|| {
+	let interval = if let Ok(interval) = std::env::var("PT_INTERVAL") {
+		if let Ok(interval) = humantime::parse_duration(&interval) {
+			interval
+		} else {
+			panic!("PT_INTERVAL env var is not a valid duration");
+		}
+	} else {
+		Duration::from_secs(10)
+	};
+
+	if interval.as_secs() > 3600 {
+		panic!("PT_INTERVAL env var is too large");
+	} else if interval.as_secs() < 1 {
+		panic!("PT_INTERVAL env var is too small");
+	}
+
+	interval
+});
+
+/// Returns whether a process should be tracked, walking the user's [`crate::matcher`] rules
+/// instead of a hardcoded ignore list. Shared by every [`crate::source::ProcessSource`] backend
+#[must_use]
+pub(crate) fn is_relevant_process(name: &str, path: &str, status: ProcessStatus, memory: u64) -> bool {
+	if path.is_empty() || status != ProcessStatus::Run {
+		return false;
+	}
+
+	let candidate = Process {
+		name: name.to_string(),
+		pretty_name: String::new(),
+		path: path.to_string(),
+		memory,
+		cpu_time: 0,
+		disk_read_bytes: 0,
+		disk_write_bytes: 0,
+		parent_name: None,
+		is_active: false,
+	};
+
+	!RULES
+		.iter()
+		.any(|rule| matches!(rule.action, Action::Ignore) && rule.matches(&candidate))
+}
+
+/// Returns a pretty version of a process executable
+#[must_use]
+pub(crate) fn pretty_process_name(path: &str, title: &str) -> String {
+	// Rename rules configured via the matcher rules take priority over everything else
+	let candidate = Process {
+		name: path.rsplit(|c| c == '\\' || c == '/').next().unwrap_or(path).to_string(),
+		pretty_name: String::new(),
+		path: path.to_string(),
+		memory: 0,
+		cpu_time: 0,
+		disk_read_bytes: 0,
+		disk_write_bytes: 0,
+		parent_name: None,
+		is_active: false,
+	};
+
+	if let Some(pretty_name) = RULES.iter().find_map(|rule| match &rule.action {
+		Action::Rename { pretty_name } if rule.matches(&candidate) => Some(pretty_name.clone()),
+		_ => None,
+	}) {
+		return pretty_name;
+	}
+
+	if title.contains(" - ") {
+		return title.split(" - ").last().unwrap().to_string();
+	} else if title.len() > 0 {
+		return title.to_string();
+	}
+
+	let name = if path.starts_with("C:\\") {
+		path.split('\\').last().unwrap().to_string()
+	} else {
+		path.to_string()
+	};
+
+	// trim the .exe extension
+	let name = name.trim_end_matches(EXTENSION).to_string();
+
+	// if name contains a separator, make it Title Case
+	if let Some(separator) = NAME_SEPARATORS.iter().find(|s| name.contains(*s)) {
+		return name
+			.split(separator)
+			.map(|part| {
+				let first_char = part.chars().next().unwrap().to_uppercase().to_string();
+				let rest = part.chars().skip(1).collect::<String>();
+
+				first_char + &rest
+			})
+			.collect::<Vec<String>>()
+			.join(" ");
+	}
+
+	// if name is all lowercase, make it Title Case
+	if name.chars().all(|c| c.is_lowercase() | c.is_numeric()) {
+		return name.chars().next().unwrap().to_uppercase().to_string() + &name.chars().skip(1).collect::<String>();
+	}
+
+	// if name is in PascalCase, make it Title Case
+	let re = Regex::new(r"([A-Z][a-z]+)").unwrap();
+	let name = re.replace_all(&name, " $1").trim_start().to_string();
+
+	// trim extra whitespace
+	name.split_whitespace()
+		.map(|s| s.to_string())
+		.collect::<Vec<String>>()
+		.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::pretty_process_name;
+
+	#[test]
+	fn test_pretty_process_name() {
+		assert_eq!(
+			pretty_process_name("chrome.exe", "Jay3 - Twitch - Google Chrome"),
+			"Google Chrome"
+		);
+		assert_eq!(
+			pretty_process_name("Discord.exe", "#general | Lurkr Support - Discord"),
+			"Discord"
+		);
+		assert_eq!(
+			pretty_process_name("LegionFanControl.exe", "LegionFanControl"),
+			"LegionFanControl"
+		);
+		assert_eq!(
+			pretty_process_name("Microsoft.SharePoint.exe", ""),
+			"Microsoft SharePoint"
+		);
+		assert_eq!(pretty_process_name("process-tracker.exe", ""), "Process Tracker");
+		assert_eq!(pretty_process_name("Razer Central.exe", ""), "Razer Central");
+		assert_eq!(pretty_process_name("ShareX.exe", "ShareX"), "ShareX");
+		assert_eq!(pretty_process_name("wallpaper32.exe", ""), "Wallpaper32");
+		assert_eq!(pretty_process_name("ui32.exe", "Wallpaper UI"), "Wallpaper UI");
+	}
+}
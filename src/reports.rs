@@ -0,0 +1,174 @@
+use rusqlite::{params, Connection, Result};
+
+/// A reporting window understood by [`usage_summary`]
+pub(crate) enum Period {
+	Day,
+	Week,
+}
+
+impl Period {
+	/// The `datetime('now', ...)` modifier for the start of this period
+	fn sqlite_modifier(&self) -> &'static str {
+		match self {
+			Period::Day => "-1 day",
+			Period::Week => "-7 days",
+		}
+	}
+}
+
+/// A tracked application's totals over a [`Period`], along with its share of that period's tracked time
+#[allow(dead_code)]
+pub(crate) struct UsageSummary {
+	pub(crate) pretty_name: String,
+	pub(crate) running_time: u64,
+	/// Time spent with the foreground window focused, as opposed to merely running in the background
+	pub(crate) active_time: u64,
+	pub(crate) cpu_time: u64,
+	pub(crate) disk_read_bytes: u64,
+	pub(crate) disk_write_bytes: u64,
+	/// This application's running time as a fraction of every tracked application's running time over the period
+	pub(crate) running_time_share: f64,
+}
+
+/// Returns each tracked application's totals for the given period, together with its share of the
+/// period's total running time
+///
+/// The total can be zero (e.g. on a fresh install, or a period with no recorded activity), in which
+/// case every share is reported as `0.0` instead of `NaN`/`inf`, via [`FiniteOr::finite_or_default`]
+pub(crate) fn usage_summary(conn: &Connection, period: Period) -> Result<Vec<UsageSummary>> {
+	// Helper subprocesses (browser renderers, webview hosts, etc.) have `parent_id` pointing at the
+	// top-level application that spawned them, so their time is attributed to the parent's
+	// `pretty_name` instead of piling up under their own
+	let mut stmt = conn.prepare(
+		"SELECT COALESCE(parent.pretty_name, processes.pretty_name), SUM(process_times.running_time), SUM(process_times.active_time), SUM(process_times.cpu_time), SUM(process_times.disk_read_bytes), SUM(process_times.disk_write_bytes)
+		FROM process_times
+		INNER JOIN processes ON processes.id = process_times.process_id
+		LEFT JOIN processes parent ON parent.id = processes.parent_id
+		WHERE process_times.created_at > datetime('now', ?1)
+		GROUP BY COALESCE(parent.pretty_name, processes.pretty_name)",
+	)?;
+
+	let mut rows = stmt.query(params![period.sqlite_modifier()])?;
+
+	let mut summaries = Vec::new();
+	let mut total_running_time: u64 = 0;
+
+	while let Some(row) = rows.next()? {
+		let running_time = row.get::<usize, u64>(1)?;
+		total_running_time += running_time;
+
+		summaries.push(UsageSummary {
+			pretty_name: row.get(0)?,
+			running_time,
+			active_time: row.get(2)?,
+			cpu_time: row.get(3)?,
+			disk_read_bytes: row.get(4)?,
+			disk_write_bytes: row.get(5)?,
+			running_time_share: 0.0,
+		});
+	}
+
+	for summary in &mut summaries {
+		summary.running_time_share = (summary.running_time as f64 / total_running_time as f64).finite_or_default(0.0);
+	}
+
+	Ok(summaries)
+}
+
+/// Extension trait that replaces non-finite floating point results (`NaN`, `inf`, `-inf`) with a default
+///
+/// Divisions like `app_time / total_time` can produce these when `total_time` is zero
+pub(crate) trait FiniteOr {
+	fn finite_or_default(self, default: f64) -> f64;
+}
+
+impl FiniteOr for f64 {
+	fn finite_or_default(self, default: f64) -> f64 {
+		if self.is_finite() {
+			self
+		} else {
+			default
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::db::create_schema;
+
+	#[test]
+	fn finite_or_default_replaces_non_finite_values() {
+		assert_eq!(f64::NAN.finite_or_default(0.0), 0.0);
+		assert_eq!(f64::INFINITY.finite_or_default(0.0), 0.0);
+		assert_eq!(f64::NEG_INFINITY.finite_or_default(0.0), 0.0);
+		assert_eq!(1.0_f64.finite_or_default(0.0), 1.0);
+	}
+
+	fn test_conn() -> Connection {
+		let conn = Connection::open_in_memory().unwrap();
+		create_schema(&conn).unwrap();
+		conn
+	}
+
+	#[test]
+	fn usage_summary_is_empty_without_any_recorded_time() {
+		let conn = test_conn();
+		conn.execute("INSERT INTO processes (name, pretty_name, path) VALUES ('a.exe', 'A', '')", ())
+			.unwrap();
+
+		let summaries = usage_summary(&conn, Period::Day).unwrap();
+
+		assert!(summaries.is_empty());
+	}
+
+	#[test]
+	fn usage_summary_reports_a_zero_share_instead_of_nan_when_total_time_is_zero() {
+		let conn = test_conn();
+		conn.execute("INSERT INTO processes (name, pretty_name, path) VALUES ('a.exe', 'A', '')", ())
+			.unwrap();
+		let process_id = conn.last_insert_rowid();
+		conn.execute("INSERT INTO process_times (process_id, running_time) VALUES (?1, 0)", params![process_id])
+			.unwrap();
+
+		let summaries = usage_summary(&conn, Period::Day).unwrap();
+
+		assert_eq!(summaries.len(), 1);
+		assert_eq!(summaries[0].running_time_share, 0.0);
+	}
+
+	#[test]
+	fn usage_summary_rolls_up_child_process_time_into_its_top_level_ancestor() {
+		let conn = test_conn();
+		conn.execute(
+			"INSERT INTO processes (name, pretty_name, path) VALUES ('chrome.exe', 'Google Chrome', 'C:\\chrome.exe')",
+			(),
+		)
+		.unwrap();
+		let parent_id = conn.last_insert_rowid();
+		conn.execute(
+			"INSERT INTO processes (name, pretty_name, path, parent_id) VALUES ('helper.exe', 'helper.exe', 'C:\\helper.exe', ?1)",
+			params![parent_id],
+		)
+		.unwrap();
+		let child_id = conn.last_insert_rowid();
+
+		conn.execute(
+			"INSERT INTO process_times (process_id, running_time) VALUES (?1, 100)",
+			params![parent_id],
+		)
+		.unwrap();
+		conn.execute(
+			"INSERT INTO process_times (process_id, running_time) VALUES (?1, 50)",
+			params![child_id],
+		)
+		.unwrap();
+
+		let summaries = usage_summary(&conn, Period::Day).unwrap();
+
+		assert_eq!(summaries.len(), 1);
+		assert_eq!(summaries[0].pretty_name, "Google Chrome");
+		assert_eq!(summaries[0].running_time, 150);
+		assert_eq!(summaries[0].running_time_share, 1.0);
+	}
+}
@@ -0,0 +1,17 @@
+#[cfg(windows)]
+mod windows;
+#[cfg(windows)]
+pub(crate) use windows::WindowsSource as DefaultSource;
+
+#[cfg(not(windows))]
+mod sysinfo_backend;
+#[cfg(not(windows))]
+pub(crate) use sysinfo_backend::SysinfoSource as DefaultSource;
+
+use crate::process::Process;
+
+/// A backend capable of enumerating the processes relevant to the user on the current platform
+pub(crate) trait ProcessSource {
+	/// Returns the list of processes currently tracked by this source
+	fn get_process_list(&self) -> Vec<Process>;
+}
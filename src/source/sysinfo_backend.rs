@@ -0,0 +1,194 @@
+use crate::process::{is_relevant_process, pretty_process_name, Process, QUERY_INTERVAL};
+use crate::source::ProcessSource;
+use std::{cell::RefCell, collections::HashSet};
+use sysinfo::{Pid, ProcessExt, System, SystemExt};
+
+/// A [`ProcessSource`] backed by `sysinfo`, tracking every running process on Linux and macOS
+pub(crate) struct SysinfoSource {
+	system: RefCell<System>,
+}
+
+impl SysinfoSource {
+	pub(crate) fn new() -> Self {
+		Self {
+			system: RefCell::new(System::new_all()),
+		}
+	}
+}
+
+impl ProcessSource for SysinfoSource {
+	fn get_process_list(&self) -> Vec<Process> {
+		let mut system = self.system.borrow_mut();
+		system.refresh_processes();
+
+		let mut seen_paths: HashSet<String> = HashSet::new();
+		let mut process_list: Vec<Process> = Vec::new();
+		let foreground_pid = foreground_pid();
+
+		for process in system.processes().values() {
+			let name = process.name();
+			let path = match process.exe().to_str() {
+				Some(path) if !path.is_empty() => path,
+				_ => continue,
+			};
+
+			if seen_paths.contains(path) {
+				continue;
+			}
+
+			let memory = process.memory();
+
+			if !is_relevant_process(name, path, process.status(), memory) {
+				continue;
+			}
+
+			let disk_usage = process.disk_usage();
+			// `cpu_usage` is a percentage over the time elapsed since the last refresh, which
+			// in practice is one query interval, so converting it to seconds is an approximation
+			let cpu_time = (f64::from(process.cpu_usage()) / 100.0 * QUERY_INTERVAL.as_secs_f64()) as u64;
+
+			// Attribute the process to its top-level ancestor instead of hand-listing known helper
+			// subprocesses as ignored. Walks up past each ancestor only while it's itself still a
+			// relevant/tracked process, so a multi-hop descendant lands on the original application
+			// without the walk running all the way up to systemd/init for everything
+			let parent_name = resolve_top_level_ancestor_name(process.pid(), |pid| {
+				let parent_pid = system.process(pid)?.parent()?;
+				let parent = system.process(parent_pid)?;
+				let parent_path = parent.exe().to_str().unwrap_or("");
+				let parent_is_relevant = is_relevant_process(parent.name(), parent_path, parent.status(), parent.memory());
+
+				Some((parent_pid, parent.name().to_string(), parent_is_relevant))
+			});
+
+			let is_active = foreground_pid == Some(process.pid());
+
+			seen_paths.insert(path.to_string());
+			process_list.push(Process {
+				name: name.to_string(),
+				pretty_name: pretty_process_name(path, name),
+				path: path.to_string(),
+				memory,
+				cpu_time,
+				disk_read_bytes: disk_usage.read_bytes,
+				disk_write_bytes: disk_usage.written_bytes,
+				parent_name,
+				is_active,
+			});
+		}
+
+		process_list
+	}
+}
+
+/// Walks from `start_pid` up through its ancestors, using `get_parent` to resolve each `(pid,
+/// name, is_relevant)` triple for the current process's parent, stopping as soon as an ancestor
+/// is no longer itself a relevant/tracked process (or the chain runs out)
+///
+/// Returns the name of the highest ancestor reached, or `None` if the immediate parent already
+/// isn't relevant (in which case the process should be attributed to itself)
+#[must_use]
+fn resolve_top_level_ancestor_name(start_pid: Pid, mut get_parent: impl FnMut(Pid) -> Option<(Pid, String, bool)>) -> Option<String> {
+	let mut current_pid = start_pid;
+	let mut ancestor_name = None;
+
+	while let Some((parent_pid, parent_name, parent_is_relevant)) = get_parent(current_pid) {
+		if !parent_is_relevant {
+			break;
+		}
+
+		current_pid = parent_pid;
+		ancestor_name = Some(parent_name);
+	}
+
+	ancestor_name
+}
+
+/// Returns the process id that owns the active/focused window, if it can be determined
+///
+/// `sysinfo` has no notion of windows, so this shells out to `xdotool` instead. Sessions where
+/// `xdotool` is missing or fails (e.g. a headless session) never report a process as active
+#[cfg(target_os = "linux")]
+fn foreground_pid() -> Option<Pid> {
+	let output = std::process::Command::new("xdotool")
+		.args(["getactivewindow", "getwindowpid"])
+		.output()
+		.ok()?;
+
+	if !output.status.success() {
+		return None;
+	}
+
+	String::from_utf8(output.stdout).ok()?.trim().parse::<usize>().ok().map(Pid::from)
+}
+
+/// Returns the process id that owns the active/focused application, if it can be determined
+#[cfg(target_os = "macos")]
+fn foreground_pid() -> Option<Pid> {
+	let output = std::process::Command::new("osascript")
+		.args(["-e", "tell application \"System Events\" to unix id of first process whose frontmost is true"])
+		.output()
+		.ok()?;
+
+	if !output.status.success() {
+		return None;
+	}
+
+	String::from_utf8(output.stdout).ok()?.trim().parse::<usize>().ok().map(Pid::from)
+}
+
+/// No known active-window query exists for this platform, so no process is ever reported as active
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn foreground_pid() -> Option<Pid> {
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	#[test]
+	fn resolve_top_level_ancestor_name_attributes_to_itself_when_parent_is_not_relevant() {
+		let parents: HashMap<Pid, (Pid, String, bool)> = [(Pid::from(2), (Pid::from(1), "systemd".to_string(), false))].into();
+
+		let ancestor = resolve_top_level_ancestor_name(Pid::from(2), |pid| parents.get(&pid).cloned());
+
+		assert_eq!(ancestor, None);
+	}
+
+	#[test]
+	fn resolve_top_level_ancestor_name_stops_at_the_first_non_relevant_ancestor() {
+		let parents: HashMap<Pid, (Pid, String, bool)> = [
+			(Pid::from(3), (Pid::from(2), "bash".to_string(), true)),
+			(Pid::from(2), (Pid::from(1), "systemd".to_string(), false)),
+		]
+		.into();
+
+		let ancestor = resolve_top_level_ancestor_name(Pid::from(3), |pid| parents.get(&pid).cloned());
+
+		assert_eq!(ancestor, Some("bash".to_string()));
+	}
+
+	#[test]
+	fn resolve_top_level_ancestor_name_walks_past_nested_relevant_ancestors() {
+		let parents: HashMap<Pid, (Pid, String, bool)> = [
+			(Pid::from(4), (Pid::from(3), "renderer".to_string(), true)),
+			(Pid::from(3), (Pid::from(2), "firefox".to_string(), true)),
+			(Pid::from(2), (Pid::from(1), "systemd".to_string(), false)),
+		]
+		.into();
+
+		let ancestor = resolve_top_level_ancestor_name(Pid::from(4), |pid| parents.get(&pid).cloned());
+
+		assert_eq!(ancestor, Some("firefox".to_string()));
+	}
+
+	#[test]
+	fn resolve_top_level_ancestor_name_stops_when_the_parent_chain_ends() {
+		let parents: HashMap<Pid, (Pid, String, bool)> = HashMap::new();
+
+		let ancestor = resolve_top_level_ancestor_name(Pid::from(2), |pid| parents.get(&pid).cloned());
+
+		assert_eq!(ancestor, None);
+	}
+}
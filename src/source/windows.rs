@@ -0,0 +1,402 @@
+use crate::process::{is_relevant_process, pretty_process_name, Process};
+use crate::source::ProcessSource;
+use std::{
+	cell::RefCell,
+	collections::{HashMap, HashSet},
+	ffi::{c_void, OsString},
+	mem::size_of,
+	os::windows::ffi::OsStringExt,
+	ptr::null_mut,
+};
+use sysinfo::ProcessStatus;
+use winapi::{
+	shared::{
+		minwindef::{DWORD, FILETIME, LPARAM, MAX_PATH},
+		ntdef::NTSTATUS,
+		ntstatus::STATUS_SUCCESS,
+		windef::HWND,
+	},
+	um::{
+		handleapi::CloseHandle,
+		processthreadsapi::{GetProcessTimes, OpenProcess},
+		psapi::{GetModuleFileNameExW, GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS},
+		winbase::GetProcessIoCounters,
+		winnt::{HANDLE, IO_COUNTERS, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ},
+		winuser::{EnumWindows, GetForegroundWindow, GetWindowTextW, GetWindowThreadProcessId, IsWindowVisible},
+	},
+};
+
+// `NtQueryInformationProcess` isn't exposed by `winapi`, so it's declared here directly
+extern "system" {
+	fn NtQueryInformationProcess(
+		process_handle: HANDLE,
+		process_information_class: u32,
+		process_information: *mut c_void,
+		process_information_length: u32,
+		return_length: *mut u32,
+	) -> NTSTATUS;
+}
+
+// Undocumented NT struct `NtQueryInformationProcess` fills in; `winapi` never exposed this
+// (there is no `winternl` module in the crate), so it's declared here by hand
+#[repr(C)]
+#[allow(non_snake_case)]
+struct PROCESS_BASIC_INFORMATION {
+	ExitStatus: NTSTATUS,
+	PebBaseAddress: *mut c_void,
+	AffinityMask: usize,
+	BasePriority: i32,
+	UniqueProcessId: usize,
+	InheritedFromUniqueProcessId: usize,
+}
+
+const PROCESS_BASIC_INFORMATION_CLASS: u32 = 0;
+
+/// The last cumulative CPU time and disk I/O counters observed for a process, used to turn the
+/// OS' lifetime totals into per-interval deltas
+#[derive(Default, Clone, Copy)]
+struct ProcessHistory {
+	cpu_time_100ns: u64,
+	read_bytes: u64,
+	write_bytes: u64,
+}
+
+/// A [`ProcessSource`] backed by `EnumWindows`, tracking only processes with a visible window
+pub(crate) struct WindowsSource {
+	history: RefCell<HashMap<DWORD, ProcessHistory>>,
+}
+
+impl WindowsSource {
+	pub(crate) fn new() -> Self {
+		Self {
+			history: RefCell::new(HashMap::new()),
+		}
+	}
+}
+
+/// A visible window surfaced by `EnumWindows`, before process info has been resolved for it
+struct VisibleWindow {
+	process_id: DWORD,
+	title: String,
+}
+
+struct EnumContext {
+	windows: Vec<VisibleWindow>,
+}
+
+impl ProcessSource for WindowsSource {
+	fn get_process_list(&self) -> Vec<Process> {
+		let mut history = self.history.borrow_mut();
+
+		let foreground_process_id = unsafe {
+			let foreground_window = GetForegroundWindow();
+			if foreground_window.is_null() {
+				0
+			} else {
+				get_process_id_for_window(foreground_window)
+			}
+		};
+
+		let mut ctx = EnumContext { windows: Vec::new() };
+
+		unsafe {
+			EnumWindows(Some(enum_windows_callback), &mut ctx as *mut EnumContext as LPARAM);
+		}
+
+		// Every process that owns at least one visible window. Resolved up front so that, below,
+		// a process' parent chain can be walked up to the highest ancestor that's still itself a
+		// windowed process, instead of walking past it to explorer.exe or beyond
+		let windowed_pids: HashSet<DWORD> = ctx.windows.iter().map(|window| window.process_id).collect();
+
+		let mut seen_paths: HashSet<String> = HashSet::new();
+
+		ctx.windows
+			.into_iter()
+			.filter_map(|window| {
+				let info = get_process_info(window.process_id, &windowed_pids)?;
+				let path = info.path.to_string_lossy().to_string();
+				let name = path.split('\\').last().unwrap_or(&path).to_string();
+
+				// A window being visible already implies the process is running
+				if !is_relevant_process(&name, &path, ProcessStatus::Run, info.memory) {
+					return None;
+				}
+
+				if seen_paths.contains(&path) {
+					return None; // Skip duplicate processes
+				}
+				seen_paths.insert(path.clone());
+
+				let current = ProcessHistory {
+					cpu_time_100ns: info.cpu_time_100ns,
+					read_bytes: info.read_bytes,
+					write_bytes: info.write_bytes,
+				};
+
+				// The first time a process is seen, there's nothing to diff it against yet. Store
+				// its lifetime counters as the baseline and report a zero delta, rather than
+				// diffing against a zeroed default and reporting the process' entire lifetime
+				// total as having happened in this one interval
+				let (cpu_time, disk_read_bytes, disk_write_bytes) = match history.insert(window.process_id, current) {
+					Some(previous) => (
+						(info.cpu_time_100ns.saturating_sub(previous.cpu_time_100ns)) / 10_000_000,
+						info.read_bytes.saturating_sub(previous.read_bytes),
+						info.write_bytes.saturating_sub(previous.write_bytes),
+					),
+					None => (0, 0, 0),
+				};
+
+				Some(Process {
+					name,
+					pretty_name: pretty_process_name(&path, &window.title),
+					path,
+					memory: info.memory,
+					cpu_time,
+					disk_read_bytes,
+					disk_write_bytes,
+					parent_name: info.parent_name,
+					is_active: window.process_id == foreground_process_id,
+				})
+			})
+			.collect::<Vec<Process>>()
+	}
+}
+
+unsafe extern "system" fn enum_windows_callback(hwnd: HWND, data: LPARAM) -> i32 {
+	let ctx = &mut *(data as *mut EnumContext);
+
+	let mut buffer: [u16; 512] = [0; 512];
+	if IsWindowVisible(hwnd) != 0 && GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32) > 0 {
+		let process_id = get_process_id_for_window(hwnd);
+		if process_id != 0 {
+			let title = OsString::from_wide(&buffer).to_string_lossy().replace("\0", "").to_string();
+			ctx.windows.push(VisibleWindow { process_id, title });
+		}
+	}
+
+	1 // Continue enumeration
+}
+
+#[must_use]
+fn get_process_id_for_window(hwnd: HWND) -> DWORD {
+	let mut process_id: DWORD = 0;
+	unsafe {
+		GetWindowThreadProcessId(hwnd, &mut process_id);
+	}
+	process_id
+}
+
+/// The raw, OS-reported, lifetime totals for a process
+struct ProcessInfo {
+	path: OsString,
+	memory: u64,
+	/// Kernel + user CPU time, in 100-nanosecond units, since the process was created
+	cpu_time_100ns: u64,
+	read_bytes: u64,
+	write_bytes: u64,
+	parent_name: Option<String>,
+}
+
+/// Returns the executable path, memory, cumulative CPU/disk counters, and parent process name for a process
+#[must_use]
+fn get_process_info(process_id: DWORD, windowed_pids: &HashSet<DWORD>) -> Option<ProcessInfo> {
+	unsafe {
+		let process_handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, process_id);
+		if process_handle.is_null() {
+			return None;
+		}
+
+		let exe_path = get_exe_path(process_handle);
+		CloseHandle(process_handle);
+		let path = exe_path?;
+
+		let process_handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, process_id);
+		if process_handle.is_null() {
+			return None;
+		}
+
+		let mut memory_counters: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+		let memory = if GetProcessMemoryInfo(process_handle, &mut memory_counters, size_of::<PROCESS_MEMORY_COUNTERS>() as DWORD) != 0 {
+			memory_counters.WorkingSetSize as u64
+		} else {
+			0
+		};
+
+		let mut creation_time: FILETIME = std::mem::zeroed();
+		let mut exit_time: FILETIME = std::mem::zeroed();
+		let mut kernel_time: FILETIME = std::mem::zeroed();
+		let mut user_time: FILETIME = std::mem::zeroed();
+		let cpu_time_100ns = if GetProcessTimes(process_handle, &mut creation_time, &mut exit_time, &mut kernel_time, &mut user_time) != 0 {
+			filetime_to_u64(kernel_time) + filetime_to_u64(user_time)
+		} else {
+			0
+		};
+
+		let mut io_counters: IO_COUNTERS = std::mem::zeroed();
+		let (read_bytes, write_bytes) = if GetProcessIoCounters(process_handle, &mut io_counters) != 0 {
+			(io_counters.ReadTransferCount, io_counters.WriteTransferCount)
+		} else {
+			(0, 0)
+		};
+
+		let parent_name = get_top_level_ancestor_name(process_id, windowed_pids);
+
+		CloseHandle(process_handle);
+
+		Some(ProcessInfo {
+			path,
+			memory,
+			cpu_time_100ns,
+			read_bytes,
+			write_bytes,
+			parent_name,
+		})
+	}
+}
+
+/// Returns the executable path for an already-open process handle
+#[must_use]
+unsafe fn get_exe_path(process_handle: HANDLE) -> Option<OsString> {
+	let mut exe_path: Vec<u16> = vec![0; MAX_PATH];
+	let exe_path_len = GetModuleFileNameExW(process_handle, null_mut(), exe_path.as_mut_ptr(), MAX_PATH as DWORD);
+
+	if exe_path_len == 0 {
+		return None;
+	}
+
+	Some(OsString::from_wide(&exe_path[..(exe_path_len as usize)]))
+}
+
+/// Returns the name of the executable for a process id, opening and closing its own handle
+#[must_use]
+fn get_process_name(process_id: DWORD) -> Option<String> {
+	unsafe {
+		let process_handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, process_id);
+		if process_handle.is_null() {
+			return None;
+		}
+
+		let path = get_exe_path(process_handle);
+		CloseHandle(process_handle);
+
+		path.map(|path| {
+			let path = path.to_string_lossy().to_string();
+			path.split('\\').last().unwrap_or(&path).to_string()
+		})
+	}
+}
+
+/// Returns the parent process id for an already-open process handle, using the undocumented but
+/// widely relied-upon `InheritedFromUniqueProcessId` field of `PROCESS_BASIC_INFORMATION`
+#[must_use]
+unsafe fn get_parent_pid(process_handle: HANDLE) -> Option<DWORD> {
+	let mut info: PROCESS_BASIC_INFORMATION = std::mem::zeroed();
+	let mut return_length: u32 = 0;
+
+	let status = NtQueryInformationProcess(
+		process_handle,
+		PROCESS_BASIC_INFORMATION_CLASS,
+		&mut info as *mut PROCESS_BASIC_INFORMATION as *mut c_void,
+		size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+		&mut return_length,
+	);
+
+	if status != STATUS_SUCCESS {
+		return None;
+	}
+
+	let parent_pid = info.InheritedFromUniqueProcessId as DWORD;
+	if parent_pid == 0 {
+		None
+	} else {
+		Some(parent_pid)
+	}
+}
+
+/// Walks the parent chain up to the highest ancestor that is itself still a windowed process, so a
+/// multi-hop descendant (e.g. a renderer spawned by a renderer spawned by the browser) attributes
+/// to the original application rather than whichever process happens to be its immediate parent.
+/// Stops as soon as an ancestor falls outside `windowed_pids` instead of continuing until it runs
+/// out of parents entirely, which would otherwise attribute ordinary top-level windows (a browser
+/// launched from the shell, a text editor, ...) to whatever launched them (typically explorer.exe,
+/// or further up still)
+#[must_use]
+unsafe fn get_top_level_ancestor_name(process_id: DWORD, windowed_pids: &HashSet<DWORD>) -> Option<String> {
+	let ancestor_pid = resolve_top_level_ancestor_pid(process_id, windowed_pids, |pid| {
+		let process_handle = OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, 0, pid);
+		if process_handle.is_null() {
+			return None;
+		}
+
+		let parent_pid = get_parent_pid(process_handle);
+		CloseHandle(process_handle);
+		parent_pid
+	});
+
+	ancestor_pid.and_then(get_process_name)
+}
+
+/// The pure part of [`get_top_level_ancestor_name`]: given a way to look up a process id's parent,
+/// walks upward only while the parent is itself in `windowed_pids`, returning the last (i.e.
+/// highest) such parent, or `None` if its immediate parent isn't windowed (or it has no parent)
+#[must_use]
+fn resolve_top_level_ancestor_pid(process_id: DWORD, windowed_pids: &HashSet<DWORD>, mut get_parent: impl FnMut(DWORD) -> Option<DWORD>) -> Option<DWORD> {
+	let mut current_pid = process_id;
+	let mut ancestor_pid = None;
+
+	while let Some(parent_pid) = get_parent(current_pid) {
+		if parent_pid == current_pid || !windowed_pids.contains(&parent_pid) {
+			break;
+		}
+
+		current_pid = parent_pid;
+		ancestor_pid = Some(parent_pid);
+	}
+
+	ancestor_pid
+}
+
+#[must_use]
+fn filetime_to_u64(filetime: FILETIME) -> u64 {
+	(u64::from(filetime.dwHighDateTime) << 32) | u64::from(filetime.dwLowDateTime)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn resolve_top_level_ancestor_pid_attributes_to_itself_when_parent_is_not_windowed() {
+		// child (1) -> explorer.exe (2, not windowed)
+		let windowed_pids: HashSet<DWORD> = [1].into_iter().collect();
+		let parents: HashMap<DWORD, DWORD> = [(1, 2)].into_iter().collect();
+
+		assert_eq!(resolve_top_level_ancestor_pid(1, &windowed_pids, |pid| parents.get(&pid).copied()), None);
+	}
+
+	#[test]
+	fn resolve_top_level_ancestor_pid_stops_at_the_first_non_windowed_ancestor() {
+		// renderer (1) -> browser (2, windowed) -> explorer.exe (3, not windowed)
+		let windowed_pids: HashSet<DWORD> = [1, 2].into_iter().collect();
+		let parents: HashMap<DWORD, DWORD> = [(1, 2), (2, 3)].into_iter().collect();
+
+		assert_eq!(resolve_top_level_ancestor_pid(1, &windowed_pids, |pid| parents.get(&pid).copied()), Some(2));
+	}
+
+	#[test]
+	fn resolve_top_level_ancestor_pid_walks_past_nested_windowed_ancestors() {
+		// nested renderer (1) -> renderer (2, windowed) -> browser (3, windowed) -> explorer.exe (4, not windowed)
+		let windowed_pids: HashSet<DWORD> = [1, 2, 3].into_iter().collect();
+		let parents: HashMap<DWORD, DWORD> = [(1, 2), (2, 3), (3, 4)].into_iter().collect();
+
+		assert_eq!(resolve_top_level_ancestor_pid(1, &windowed_pids, |pid| parents.get(&pid).copied()), Some(3));
+	}
+
+	#[test]
+	fn resolve_top_level_ancestor_pid_stops_when_the_parent_chain_ends() {
+		let windowed_pids: HashSet<DWORD> = [1].into_iter().collect();
+		let parents: HashMap<DWORD, DWORD> = HashMap::new();
+
+		assert_eq!(resolve_top_level_ancestor_pid(1, &windowed_pids, |pid| parents.get(&pid).copied()), None);
+	}
+}